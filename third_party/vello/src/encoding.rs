@@ -6,18 +6,89 @@
 use crate::ffi;
 use {
     peniko::{
+        kurbo,
         kurbo::{Affine, Cap, Join, PathEl, Point, Stroke},
-        Brush, Color, Fill, Mix,
+        BlendMode, Brush, Color, ColorStop, ColorStops, Compose, Extend, Fill, Format, Gradient,
+        GradientKind, Image, Mix,
     },
+    std::collections::HashMap,
     std::pin::Pin,
+    std::sync::Arc,
     vello_encoding::{
-        BumpEstimator, Encoding as VelloEncoding, PathEncoder, RenderConfig, Transform,
+        BumpEstimator, Encoding as VelloEncoding, Font, Glyph, NormalizedCoord, PathEncoder,
+        RenderConfig, Resolver, Transform,
     },
 };
 
 pub(crate) struct Encoding {
     encoding: VelloEncoding,
     estimator: BumpEstimator,
+    images: HashMap<u64, Image>,
+    has_gradients: bool,
+    // The logical contents behind `encoding`, recorded as they're encoded so that `decode` can
+    // play them back without having to parse the packed tag/data streams. See `decode`.
+    ops: Vec<DecodedOp>,
+}
+
+// One logical operation recorded alongside `Encoding::encoding`, in encode order. `Encoding::decode`
+// replays these against a `ffi::SceneVisitor` instead of reaching into the packed scene blob.
+#[derive(Clone)]
+enum DecodedOp {
+    Transform(ffi::Affine),
+    PathSegment(ffi::PathElement),
+    FillStyle(ffi::Fill),
+    StrokeStyle(ffi::Stroke),
+    Draw(DecodedBrush),
+    Glyphs(DecodedGlyphRun),
+    BeginClip,
+    // Distinct from `BeginClip` so `decode` can tell a translucent/blended layer apart from a
+    // plain clip -- both go through `encode_begin_clip` on the packed-encoding side, but only this
+    // variant carries the `blend_mode`/`alpha` chunk0-2 added.
+    BeginLayer(ffi::BlendMode, f32),
+    EndClip,
+}
+
+// The payload of a `DecodedOp::Glyphs`, capturing everything `draw_glyphs` fed into
+// `encode_glyph_run` so `decode` can replay a text run instead of silently dropping it.
+#[derive(Clone)]
+struct DecodedGlyphRun {
+    transform: ffi::Affine,
+    glyph_transform: Option<ffi::Affine>,
+    font: Font,
+    font_size: f32,
+    normalized_coords: Vec<NormalizedCoord>,
+    style: ffi::Fill,
+    brush: DecodedBrush,
+    glyphs: Vec<ffi::Glyph>,
+}
+
+// The brush payload for a `DecodedOp::Draw`, keyed the same way as `ffi::Brush`/`ffi::BrushKind`.
+#[derive(Clone)]
+enum DecodedBrush {
+    Solid(ffi::Color),
+    LinearGradient(ffi::LinearGradient),
+    RadialGradient(ffi::RadialGradient),
+    SweepGradient(ffi::SweepGradient),
+    Image(ffi::ImageBrush),
+}
+
+// Captures the logical payload of an `ffi::Brush` for later replay through `decode`, mirroring the
+// `ffi::BrushKind` cases handled by `Encoding::brush` below.
+fn decode_brush(brush: &ffi::Brush) -> DecodedBrush {
+    match brush.kind {
+        ffi::BrushKind::Solid => DecodedBrush::Solid(brush.data.solid.clone()),
+        ffi::BrushKind::LinearGradient => {
+            DecodedBrush::LinearGradient(brush.data.linear_gradient.clone())
+        }
+        ffi::BrushKind::RadialGradient => {
+            DecodedBrush::RadialGradient(brush.data.radial_gradient.clone())
+        }
+        ffi::BrushKind::SweepGradient => {
+            DecodedBrush::SweepGradient(brush.data.sweep_gradient.clone())
+        }
+        ffi::BrushKind::Image => DecodedBrush::Image(brush.data.image.clone()),
+        _ => panic!("invalid brush kind"),
+    }
 }
 
 pub(crate) fn new_encoding() -> Box<Encoding> {
@@ -32,7 +103,21 @@ impl Encoding {
         // the encoding as non-fragment achieves this.
         let mut encoding = VelloEncoding::new();
         encoding.reset();
-        Encoding { encoding, estimator: BumpEstimator::new(), }
+        Encoding {
+            encoding,
+            estimator: BumpEstimator::new(),
+            images: HashMap::new(),
+            has_gradients: false,
+            ops: Vec::new(),
+        }
+    }
+
+    // Registers decoded RGBA8 pixel data under `image_id`, so that a later `ffi::BrushKind::Image`
+    // brush referencing that id can be resolved into a `peniko::Image` during `fill`/`stroke`.
+    pub fn register_image(&mut self, image_id: u64, width: u32, height: u32, pixels: &[u8]) {
+        let data = peniko::Blob::new(Arc::new(pixels.to_vec()));
+        self.images
+            .insert(image_id, Image::new(data, Format::Rgba8, width, height));
     }
 
     pub fn is_empty(&self) -> bool {
@@ -42,6 +127,8 @@ impl Encoding {
     pub fn reset(&mut self) {
         self.encoding.reset();
         self.estimator.reset();
+        self.has_gradients = false;
+        self.ops.clear();
     }
 
     pub fn fill(
@@ -54,8 +141,13 @@ impl Encoding {
         let t = Transform::from_kurbo(&transform.into());
         self.encoding.encode_transform(t);
         self.encoding.encode_fill_style(style.into());
+        self.ops.push(DecodedOp::Transform(transform));
+        self.ops.push(DecodedOp::FillStyle(style));
         if self.encode_path(path_iter, &t, None) {
-            self.encoding.encode_brush(&Brush::from(brush), 1.0)
+            let decoded_brush = decode_brush(brush);
+            let brush = self.brush(brush);
+            self.encoding.encode_brush(&brush, 1.0);
+            self.ops.push(DecodedOp::Draw(decoded_brush));
         }
     }
 
@@ -68,13 +160,34 @@ impl Encoding {
     ) {
         let t = Transform::from_kurbo(&transform.into());
         self.encoding.encode_transform(t);
+        self.ops.push(DecodedOp::Transform(transform));
 
-        // TODO: process any dash pattern here using kurbo's dash expander unless Graphite
-        // handles dashing already.
-        let stroke = style.into();
+        let stroke: Stroke = style.into();
         self.encoding.encode_stroke_style(&stroke);
-        if self.encode_path(path_iter, &t, Some(&stroke)) {
-            self.encoding.encode_brush(&Brush::from(brush), 1.0);
+        self.ops.push(DecodedOp::StrokeStyle(style.clone()));
+
+        // An all-zero (or empty) dash array has no visible effect, and kurbo's dash expander
+        // isn't guaranteed to terminate sanely on a zero-length cycle, so treat it the same as
+        // "no dashing".
+        let has_dashes = stroke.dash_pattern.iter().any(|&len| len > 0.0);
+        let encoded = if !has_dashes {
+            self.encode_path(path_iter, &t, Some(&stroke))
+        } else {
+            // Graphite doesn't pre-expand dashes, so run the path through kurbo's dash
+            // expander before feeding it to the encoder and the bump estimator.
+            let elements: Vec<PathEl> = path_iter.collect();
+            let dashed = kurbo::dash(
+                elements.into_iter(),
+                stroke.dash_offset,
+                &stroke.dash_pattern,
+            );
+            self.encode_path_elements(dashed, &t, Some(&stroke))
+        };
+        if encoded {
+            let decoded_brush = decode_brush(brush);
+            let brush = self.brush(brush);
+            self.encoding.encode_brush(&brush, 1.0);
+            self.ops.push(DecodedOp::Draw(decoded_brush));
         }
     }
 
@@ -82,17 +195,150 @@ impl Encoding {
         let t = Transform::from_kurbo(&transform.into());
         self.encoding.encode_transform(t);
         self.encoding.encode_fill_style(Fill::NonZero);
+        self.ops.push(DecodedOp::Transform(transform));
+        self.ops.push(DecodedOp::FillStyle(ffi::Fill::NonZero));
         self.encode_path(path_iter, &t, None);
         self.encoding.encode_begin_clip(Mix::Clip.into(), /*alpha=*/ 1.0);
+        self.ops.push(DecodedOp::BeginClip);
     }
 
     pub fn end_clip(&mut self) {
         self.encoding.encode_end_clip();
+        self.ops.push(DecodedOp::EndClip);
+    }
+
+    pub fn begin_layer(
+        &mut self,
+        transform: ffi::Affine,
+        blend_mode: ffi::BlendMode,
+        alpha: f32,
+        path_iter: Pin<&mut ffi::PathIterator>,
+    ) {
+        let t = Transform::from_kurbo(&transform.into());
+        self.encoding.encode_transform(t);
+        self.encoding.encode_fill_style(Fill::NonZero);
+        self.ops.push(DecodedOp::Transform(transform));
+        self.ops.push(DecodedOp::FillStyle(ffi::Fill::NonZero));
+        self.encode_path(path_iter, &t, None);
+        self.encoding
+            .encode_begin_clip(BlendMode::from(blend_mode).into(), alpha);
+        self.ops.push(DecodedOp::BeginLayer(blend_mode, alpha));
+    }
+
+    pub fn end_layer(&mut self) {
+        self.end_clip();
+    }
+
+    pub fn draw_glyphs(
+        &mut self,
+        font: &ffi::Font,
+        font_size: f32,
+        transform: ffi::Affine,
+        glyph_transform: Option<ffi::Affine>,
+        normalized_coords: &[NormalizedCoord],
+        brush: &ffi::Brush,
+        glyphs: &[ffi::Glyph],
+    ) -> bool {
+        let t = Transform::from_kurbo(&transform.into());
+        let glyph_transform_t = glyph_transform.map(|gt| Transform::from_kurbo(&gt.into()));
+        let font: Font = font.into();
+        let style = Fill::NonZero;
+        let encoded_glyphs: Vec<Glyph> = glyphs
+            .iter()
+            .map(|g| Glyph {
+                id: g.glyph_id,
+                x: g.x,
+                y: g.y,
+            })
+            .collect();
+
+        // `encode_glyph_run` decodes and encodes glyph outlines internally rather than routing
+        // them through `encode_path_elements`, so the bump estimator needs its own pass over the
+        // same glyphs to account for the additional path segments.
+        self.estimator.count_glyphs(
+            &font,
+            encoded_glyphs.iter().copied(),
+            normalized_coords,
+            font_size,
+            /*hint=*/ false,
+            style,
+            &t,
+            glyph_transform_t.as_ref(),
+        );
+
+        let decoded_brush = decode_brush(brush);
+        let resolved_brush = self.brush(brush);
+        let drew = self.encoding.encode_glyph_run(
+            &font,
+            encoded_glyphs.into_iter(),
+            normalized_coords,
+            font_size,
+            /*hint=*/ false,
+            style,
+            &resolved_brush,
+            /*brush_alpha=*/ 1.0,
+            t,
+            glyph_transform_t,
+        );
+
+        // Glyph runs don't go through `fill`/`stroke`'s path-segment recording, so record the
+        // run's `Transform` followed by the whole run as one `Glyphs` op, in place of the
+        // style/path/`Draw` ops that `fill` records.
+        self.ops.push(DecodedOp::Transform(transform));
+        self.ops.push(DecodedOp::Glyphs(DecodedGlyphRun {
+            transform,
+            glyph_transform,
+            font,
+            font_size,
+            normalized_coords: normalized_coords.to_vec(),
+            style: ffi::Fill::NonZero,
+            brush: decoded_brush,
+            glyphs: glyphs.to_vec(),
+        }));
+
+        drew
     }
 
     pub fn append(&mut self, other: &Encoding) {
         self.encoding.append(&other.encoding, &None);
         self.estimator.append(&other.estimator, None);
+        self.ops.extend(other.ops.iter().cloned());
+        self.has_gradients |= other.has_gradients;
+        for (image_id, image) in &other.images {
+            self.images.entry(*image_id).or_insert_with(|| image.clone());
+        }
+    }
+
+    // Walks the logical contents recorded alongside the packed encoding -- transforms, path
+    // segments (as encoded, curves included), fill/stroke styles, and draw brush payloads, in the
+    // order they were encoded -- without reaching into the opaque packed scene blob. Intended for
+    // CPU-side processing, debugging, and golden-image testing.
+    pub fn decode(&self, mut visitor: Pin<&mut ffi::SceneVisitor>) {
+        for op in &self.ops {
+            unsafe {
+                match op {
+                    DecodedOp::Transform(t) => visitor.as_mut().visit_transform(t),
+                    DecodedOp::PathSegment(el) => visitor.as_mut().visit_path_segment(el),
+                    DecodedOp::FillStyle(fill) => visitor.as_mut().visit_fill_style(*fill),
+                    DecodedOp::StrokeStyle(stroke) => visitor.as_mut().visit_stroke_style(stroke),
+                    DecodedOp::Draw(brush) => visitor.as_mut().visit_draw(&brush.into()),
+                    DecodedOp::Glyphs(run) => visitor.as_mut().visit_draw_glyphs(
+                        &run.transform,
+                        run.glyph_transform.as_ref(),
+                        run.font_size,
+                        &run.normalized_coords,
+                        run.style,
+                        &(&run.brush).into(),
+                        &run.glyphs,
+                    ),
+                    DecodedOp::BeginClip => visitor.as_mut().visit_begin_clip(),
+                    DecodedOp::BeginLayer(blend_mode, alpha) => {
+                        visitor.as_mut().visit_begin_layer(blend_mode, *alpha)
+                    }
+                    DecodedOp::EndClip => visitor.as_mut().visit_end_clip(),
+                }
+            }
+        }
     }
 
     pub fn prepare_render(
@@ -102,7 +348,29 @@ impl Encoding {
         background: &ffi::Color,
     ) -> Box<RenderConfiguration> {
         let mut packed_scene = Vec::new();
-        let layout = vello_encoding::resolve_solid_paths_only(&self.encoding, &mut packed_scene);
+        let mut ramps = Atlas::default();
+        let mut atlas_images = Atlas::default();
+        // A scene with only solid brushes (no gradients or images) can skip straight past
+        // ramp/atlas resolution; once either is present we need the full resolve to pack their
+        // pixel data into the scene buffer.
+        let layout = if self.images.is_empty() && !self.has_gradients {
+            vello_encoding::resolve_solid_paths_only(&self.encoding, &mut packed_scene)
+        } else {
+            let mut resolver = Resolver::new();
+            let (layout, resolved_ramps, resolved_images) =
+                resolver.resolve(&self.encoding, &mut packed_scene);
+            ramps = Atlas {
+                width: resolved_ramps.width,
+                height: resolved_ramps.height,
+                data: bytemuck::cast_slice(&resolved_ramps.data).to_vec(),
+            };
+            atlas_images = Atlas {
+                width: resolved_images.width,
+                height: resolved_images.height,
+                data: bytemuck::cast_slice(&resolved_images.data).to_vec(),
+            };
+            layout
+        };
         let mut config = RenderConfig::new(&layout, width, height, &background.into());
 
         let bump_estimate = self.estimator.tally(None);
@@ -119,17 +387,72 @@ impl Encoding {
         Box::new(RenderConfiguration {
             packed_scene,
             config,
+            ramps,
+            atlas_images,
         })
     }
 
+    // Resolves an `ffi::Brush` into a `peniko::Brush`, filling in the registered image data for
+    // `ffi::BrushKind::Image` brushes (which `From<&ffi::Brush>` can't do on its own, since it
+    // has no access to `self.images`), and tracking whether `prepare_render` needs the full
+    // resolve pass to pack gradient ramps.
+    fn brush(&mut self, brush: &ffi::Brush) -> Brush {
+        match brush.kind {
+            ffi::BrushKind::LinearGradient
+            | ffi::BrushKind::RadialGradient
+            | ffi::BrushKind::SweepGradient => {
+                self.has_gradients = true;
+                Brush::from(brush)
+            }
+            ffi::BrushKind::Image => {
+                let image_brush = &brush.data.image;
+                let image = self
+                    .images
+                    .get(&image_brush.image_id)
+                    .expect("draw referenced an unregistered image id");
+                // Use the dimensions recorded at `register_image` time, which describe the
+                // actual layout of `image.data` -- trusting the per-draw `ImageBrush` dimensions
+                // instead would let a mismatched draw call make the atlas packer read out of
+                // bounds.
+                Brush::Image(Image {
+                    data: image.data.clone(),
+                    format: image.format,
+                    width: image.width,
+                    height: image.height,
+                    x_extend: image_brush.x_extend.into(),
+                    y_extend: image_brush.y_extend.into(),
+                    alpha: image_brush.alpha,
+                })
+            }
+            _ => Brush::from(brush),
+        }
+    }
+
     fn encode_path(
         &mut self,
         iter: Pin<&mut ffi::PathIterator>,
         transform: &Transform,
         stroke: Option<&Stroke>,
+    ) -> bool {
+        self.encode_path_elements(iter, transform, stroke)
+    }
+
+    // Like `encode_path`, but takes any source of path elements rather than just the raw FFI
+    // iterator, so that e.g. a dash-expanded path can be fed through the same encoder/estimator
+    // plumbing.
+    fn encode_path_elements<I: Iterator<Item = PathEl>>(
+        &mut self,
+        iter: I,
+        transform: &Transform,
+        stroke: Option<&Stroke>,
     ) -> bool {
         let mut encoder = self.encoding.encode_path(/*is_fill=*/ stroke.is_none());
 
+        // Record each path element into `self.ops` as it's pulled through, so `decode` can
+        // later replay the same path without parsing the packed path-tag/data streams.
+        let ops = &mut self.ops;
+        let iter = iter.inspect(|&el| ops.push(DecodedOp::PathSegment(el.into())));
+
         // Wrap the input iterator inside a custom iterator, so that the path gets
         // encoded as the estimator runs through it.
         let path = IterablePathEncoder { iter, encoder: &mut encoder };
@@ -139,55 +462,43 @@ impl Encoding {
 }
 
 // This is path element iterator that encodes path elements as it gets polled.
-struct IterablePathEncoder<'a, 'b> {
-    iter: Pin<&'a mut ffi::PathIterator>,
-    encoder: &'a mut PathEncoder<'b>,
+struct IterablePathEncoder<'b, I: Iterator<Item = PathEl>> {
+    iter: I,
+    encoder: &'b mut PathEncoder<'b>,
 }
 
-impl Iterator for IterablePathEncoder<'_, '_> {
+impl<I: Iterator<Item = PathEl>> Iterator for IterablePathEncoder<'_, I> {
     type Item = PathEl;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut path_el = ffi::PathElement::default();
-        if !unsafe { self.iter.as_mut().next_element(&mut path_el) } {
-            return None;
-        }
-        Some(match path_el.verb {
-            ffi::PathVerb::MoveTo => {
-                let p = &path_el.points[0];
-                self.encoder.move_to(p.x, p.y);
-                PathEl::MoveTo(p.into())
+        let path_el = self.iter.next()?;
+        match path_el {
+            PathEl::MoveTo(p) => self.encoder.move_to(p.x, p.y),
+            PathEl::LineTo(p) => self.encoder.line_to(p.x, p.y),
+            PathEl::QuadTo(p0, p1) => self.encoder.quad_to(p0.x, p0.y, p1.x, p1.y),
+            PathEl::CurveTo(p0, p1, p2) => {
+                self.encoder.cubic_to(p0.x, p0.y, p1.x, p1.y, p2.x, p2.y)
             }
-            ffi::PathVerb::LineTo => {
-                let p = &path_el.points[1];
-                self.encoder.line_to(p.x, p.y);
-                PathEl::LineTo(p.into())
-            }
-            ffi::PathVerb::QuadTo => {
-                let p0 = &path_el.points[1];
-                let p1 = &path_el.points[2];
-                self.encoder.quad_to(p0.x, p0.y, p1.x, p1.y);
-                PathEl::QuadTo(p0.into(), p1.into())
-            }
-            ffi::PathVerb::CurveTo => {
-                let p0 = &path_el.points[1];
-                let p1 = &path_el.points[2];
-                let p2 = &path_el.points[3];
-                self.encoder.cubic_to(p0.x, p0.y, p1.x, p1.y, p2.x, p2.y);
-                PathEl::CurveTo(p0.into(), p1.into(), p2.into())
-            }
-            ffi::PathVerb::Close => {
-                self.encoder.close();
-                PathEl::ClosePath
-            }
-            _ => panic!("invalid path verb"),
-        })
+            PathEl::ClosePath => self.encoder.close(),
+        }
+        Some(path_el)
     }
 }
 
+// A packed pixel buffer for a gradient-ramp or image atlas texture, produced by the full
+// `Resolver::resolve` pass alongside the packed scene buffer.
+#[derive(Default)]
+struct Atlas {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
 pub(crate) struct RenderConfiguration {
     packed_scene: Vec<u8>,
     config: RenderConfig,
+    ramps: Atlas,
+    atlas_images: Atlas,
 }
 
 impl RenderConfiguration {
@@ -223,6 +534,46 @@ impl RenderConfiguration {
     pub fn buffer_sizes(self: &RenderConfiguration) -> ffi::BufferSizes {
         (&self.config.buffer_sizes).into()
     }
+
+    pub fn ramp_atlas_width(self: &RenderConfiguration) -> u32 {
+        self.ramps.width
+    }
+
+    pub fn ramp_atlas_height(self: &RenderConfiguration) -> u32 {
+        self.ramps.height
+    }
+
+    pub fn ramp_atlas_buffer_size(self: &RenderConfiguration) -> usize {
+        self.ramps.data.len()
+    }
+
+    pub fn write_ramp_atlas_buffer(self: &RenderConfiguration, out_buffer: &mut [u8]) -> bool {
+        if out_buffer.len() < self.ramps.data.len() {
+            return false;
+        }
+        out_buffer.copy_from_slice(&self.ramps.data);
+        true
+    }
+
+    pub fn image_atlas_width(self: &RenderConfiguration) -> u32 {
+        self.atlas_images.width
+    }
+
+    pub fn image_atlas_height(self: &RenderConfiguration) -> u32 {
+        self.atlas_images.height
+    }
+
+    pub fn image_atlas_buffer_size(self: &RenderConfiguration) -> usize {
+        self.atlas_images.data.len()
+    }
+
+    pub fn write_image_atlas_buffer(self: &RenderConfiguration, out_buffer: &mut [u8]) -> bool {
+        if out_buffer.len() < self.atlas_images.data.len() {
+            return false;
+        }
+        out_buffer.copy_from_slice(&self.atlas_images.data);
+        true
+    }
 }
 
 impl Iterator for Pin<&mut ffi::PathIterator> {
@@ -265,6 +616,49 @@ impl From<&ffi::Point> for Point {
     }
 }
 
+impl From<Point> for ffi::Point {
+    fn from(src: Point) -> Self {
+        Self {
+            x: src.x as f32,
+            y: src.y as f32,
+        }
+    }
+}
+
+// Reverses the `ffi::PathElement` -> `PathEl` conversion in `impl Iterator for
+// Pin<&mut ffi::PathIterator>` above, using the same point-slot layout (only `points[0]` is used
+// for `MoveTo`; the others reuse `points[1..]`).
+impl From<PathEl> for ffi::PathElement {
+    fn from(src: PathEl) -> Self {
+        let mut el = ffi::PathElement::default();
+        match src {
+            PathEl::MoveTo(p) => {
+                el.verb = ffi::PathVerb::MoveTo;
+                el.points[0] = p.into();
+            }
+            PathEl::LineTo(p) => {
+                el.verb = ffi::PathVerb::LineTo;
+                el.points[1] = p.into();
+            }
+            PathEl::QuadTo(p0, p1) => {
+                el.verb = ffi::PathVerb::QuadTo;
+                el.points[1] = p0.into();
+                el.points[2] = p1.into();
+            }
+            PathEl::CurveTo(p0, p1, p2) => {
+                el.verb = ffi::PathVerb::CurveTo;
+                el.points[1] = p0.into();
+                el.points[2] = p1.into();
+                el.points[3] = p2.into();
+            }
+            PathEl::ClosePath => {
+                el.verb = ffi::PathVerb::Close;
+            }
+        }
+        el
+    }
+}
+
 impl Default for ffi::PathVerb {
     fn default() -> Self {
         Self::MoveTo
@@ -299,11 +693,114 @@ impl From<&ffi::Brush> for Brush {
     fn from(src: &ffi::Brush) -> Self {
         match src.kind {
             ffi::BrushKind::Solid => Brush::Solid(Color::from(&src.data.solid)),
+            ffi::BrushKind::LinearGradient => {
+                Brush::Gradient(Gradient::from(&src.data.linear_gradient))
+            }
+            ffi::BrushKind::RadialGradient => {
+                Brush::Gradient(Gradient::from(&src.data.radial_gradient))
+            }
+            ffi::BrushKind::SweepGradient => {
+                Brush::Gradient(Gradient::from(&src.data.sweep_gradient))
+            }
             _ => panic!("invalid brush kind"),
         }
     }
 }
 
+// Reverses `decode_brush`, reconstructing the `ffi::Brush` tagged union for replay through
+// `ffi::SceneVisitor::visit_draw`.
+impl From<&DecodedBrush> for ffi::Brush {
+    fn from(src: &DecodedBrush) -> Self {
+        let mut data = ffi::BrushData::default();
+        let kind = match src {
+            DecodedBrush::Solid(color) => {
+                data.solid = color.clone();
+                ffi::BrushKind::Solid
+            }
+            DecodedBrush::LinearGradient(gradient) => {
+                data.linear_gradient = gradient.clone();
+                ffi::BrushKind::LinearGradient
+            }
+            DecodedBrush::RadialGradient(gradient) => {
+                data.radial_gradient = gradient.clone();
+                ffi::BrushKind::RadialGradient
+            }
+            DecodedBrush::SweepGradient(gradient) => {
+                data.sweep_gradient = gradient.clone();
+                ffi::BrushKind::SweepGradient
+            }
+            DecodedBrush::Image(image) => {
+                data.image = image.clone();
+                ffi::BrushKind::Image
+            }
+        };
+        ffi::Brush { kind, data }
+    }
+}
+
+impl From<ffi::ExtendMode> for Extend {
+    fn from(src: ffi::ExtendMode) -> Self {
+        match src {
+            ffi::ExtendMode::Pad => Self::Pad,
+            ffi::ExtendMode::Repeat => Self::Repeat,
+            ffi::ExtendMode::Reflect => Self::Reflect,
+            _ => panic!("invalid extend mode"),
+        }
+    }
+}
+
+fn color_stops(stops: &[ffi::ColorStop]) -> ColorStops {
+    stops
+        .iter()
+        .map(|stop| ColorStop {
+            offset: stop.offset,
+            color: Color::from(&stop.color),
+        })
+        .collect()
+}
+
+impl From<&ffi::LinearGradient> for Gradient {
+    fn from(src: &ffi::LinearGradient) -> Self {
+        Self {
+            kind: GradientKind::Linear {
+                start: Point::from(&src.start),
+                end: Point::from(&src.end),
+            },
+            extend: src.extend.into(),
+            stops: color_stops(&src.stops),
+        }
+    }
+}
+
+impl From<&ffi::RadialGradient> for Gradient {
+    fn from(src: &ffi::RadialGradient) -> Self {
+        Self {
+            kind: GradientKind::Radial {
+                start_center: Point::from(&src.start_center),
+                start_radius: src.start_radius,
+                end_center: Point::from(&src.end_center),
+                end_radius: src.end_radius,
+            },
+            extend: src.extend.into(),
+            stops: color_stops(&src.stops),
+        }
+    }
+}
+
+impl From<&ffi::SweepGradient> for Gradient {
+    fn from(src: &ffi::SweepGradient) -> Self {
+        Self {
+            kind: GradientKind::Sweep {
+                center: Point::from(&src.center),
+                start_angle: src.start_angle,
+                end_angle: src.end_angle,
+            },
+            extend: src.extend.into(),
+            stops: color_stops(&src.stops),
+        }
+    }
+}
+
 impl From<ffi::Fill> for Fill {
     fn from(src: ffi::Fill) -> Self {
         match src {
@@ -333,10 +830,70 @@ impl From<&ffi::Stroke> for Stroke {
             miter_limit: src.miter_limit as f64,
             start_cap: cap,
             end_cap: cap,
-            // Skia expands a dash effect by transforming the encoded path, so don't need to handle
-            // that here.
-            dash_pattern: Default::default(),
-            dash_offset: 0.,
+            dash_pattern: src.dash_pattern.iter().map(|&w| w as f64).collect(),
+            dash_offset: src.dash_offset as f64,
+        }
+    }
+}
+
+impl From<&ffi::Font> for Font {
+    fn from(src: &ffi::Font) -> Self {
+        let data = peniko::Blob::new(std::sync::Arc::new(src.data.to_vec()));
+        Self::new(data, src.index)
+    }
+}
+
+impl From<ffi::MixMode> for Mix {
+    fn from(src: ffi::MixMode) -> Self {
+        match src {
+            ffi::MixMode::Normal => Self::Normal,
+            ffi::MixMode::Multiply => Self::Multiply,
+            ffi::MixMode::Screen => Self::Screen,
+            ffi::MixMode::Overlay => Self::Overlay,
+            ffi::MixMode::Darken => Self::Darken,
+            ffi::MixMode::Lighten => Self::Lighten,
+            ffi::MixMode::ColorDodge => Self::ColorDodge,
+            ffi::MixMode::ColorBurn => Self::ColorBurn,
+            ffi::MixMode::HardLight => Self::HardLight,
+            ffi::MixMode::SoftLight => Self::SoftLight,
+            ffi::MixMode::Difference => Self::Difference,
+            ffi::MixMode::Exclusion => Self::Exclusion,
+            ffi::MixMode::Hue => Self::Hue,
+            ffi::MixMode::Saturation => Self::Saturation,
+            ffi::MixMode::Color => Self::Color,
+            ffi::MixMode::Luminosity => Self::Luminosity,
+            ffi::MixMode::Clip => Self::Clip,
+            _ => panic!("invalid mix mode"),
+        }
+    }
+}
+
+impl From<ffi::ComposeMode> for Compose {
+    fn from(src: ffi::ComposeMode) -> Self {
+        match src {
+            ffi::ComposeMode::Clear => Self::Clear,
+            ffi::ComposeMode::Copy => Self::Copy,
+            ffi::ComposeMode::Dest => Self::Dest,
+            ffi::ComposeMode::SrcOver => Self::SrcOver,
+            ffi::ComposeMode::DestOver => Self::DestOver,
+            ffi::ComposeMode::SrcIn => Self::SrcIn,
+            ffi::ComposeMode::DestIn => Self::DestIn,
+            ffi::ComposeMode::SrcOut => Self::SrcOut,
+            ffi::ComposeMode::DestOut => Self::DestOut,
+            ffi::ComposeMode::SrcAtop => Self::SrcAtop,
+            ffi::ComposeMode::DestAtop => Self::DestAtop,
+            ffi::ComposeMode::Xor => Self::Xor,
+            ffi::ComposeMode::Plus => Self::Plus,
+            _ => panic!("invalid compose operator"),
+        }
+    }
+}
+
+impl From<ffi::BlendMode> for BlendMode {
+    fn from(src: ffi::BlendMode) -> Self {
+        Self {
+            mix: src.mix.into(),
+            compose: src.compose.into(),
         }
     }
 }